@@ -5,6 +5,52 @@
 //! expression using those constants.
 //!
 //! The [`try_constify`] macro does the same, but permits errors when the input is out-of-range.
+//!
+//! Enabling the `alloc` feature changes the error type of [`try_constify`] from `&'static str` to
+//! `alloc::string::String`, allowing the offending runtime value to be formatted into the error
+//! message.
+//!
+//! A constant list may also be given as a range, `$start..=$end` or `$start..=$end step $step`, in
+//! which case it's expanded into the same match arms as an equivalent explicit list, at
+//! macro-expansion time. Ranges are bounded to `0..=64`. Expanding a range recurses a number of
+//! times that grows linearly with both `$start` and `$end - $start` (a larger `$step` lowers it
+//! only modestly), so the default `#![recursion_limit]` (128) covers `0..=8` but not, for example,
+//! `0..=9` or `20..=28`; wider or higher ranges may need the invoking crate to raise it.
+
+mod range;
+
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub extern crate alloc as __alloc;
+
+/// Build the error value returned by the `@error` tree of [`__impl_constify`] when a runtime
+/// value doesn't match any of the provided constants.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_err {
+    ($const_var:ident, $value:expr) => {
+        ::core::result::Result::Err($crate::__alloc::format!(
+            concat!("unexpected value for `", stringify!($const_var), "`: {}"),
+            $value
+        ))
+    };
+    ($const_var:ident, $value:expr, $fmt:literal) => {
+        ::core::result::Result::Err($crate::__alloc::format!($fmt, $value))
+    };
+}
+
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_err {
+    ($const_var:ident, $value:expr) => {
+        ::core::result::Result::Err(concat!("unexpected value for `", stringify!($const_var), "`"))
+    };
+    ($const_var:ident, $value:expr, $fmt:literal) => {
+        ::core::result::Result::Err($fmt)
+    };
+}
 
 #[doc(hidden)]
 #[macro_export]
@@ -12,14 +58,14 @@ macro_rules! __impl_constify {
     // Pull off the first const variable
     {
         @$mode:ident
-        [{ const $const_var:ident: $const_ty:ty = $match_val:expr => $($const_expr:expr),+; } $($rest:tt)*]
-        $block:block
+        [{ const $const_var:ident: $const_ty:ty = $match_val:expr => $($const_expr:expr),+ $(, $(: $msg:literal)? $(else $err:expr)?)?; } $($rest:tt)*]
+        $block:block $($fallback:block)?
     } => {
         $crate::__impl_constify! {
             @$mode
-            const $const_var: $const_ty = $match_val => $($const_expr),*;
+            const $const_var: $const_ty = $match_val => $($const_expr),* $(, $(: $msg)? $(else $err)?)?;
             [$($rest)*]
-            $block
+            $block $($fallback)?
         }
     };
 
@@ -48,10 +94,58 @@ macro_rules! __impl_constify {
         $block
     };
 
+    // Implement the "fallback" tree: a miss evaluates the caller-provided fallback expression
+    // instead of requiring every variant to be covered
+    {
+        @fallback
+        const $const_var:ident: $const_ty:ty = $match_val:expr => $($const_expr:expr),+;
+        $rest:tt
+        $block:block $fallback:block
+    } => {
+        match $match_val {
+            $(
+            $const_expr => {
+                const $const_var: $const_ty = $const_expr;
+                $crate::__impl_constify! { @fallback $rest $block $fallback }
+            }
+            )*
+            #[allow(unreachable_patterns)]
+            _ => $fallback,
+        }
+    };
+
+    // Terminate the "fallback" tree
+    {
+        @fallback [] $block:block $fallback:block
+    } => {
+        $block
+    };
+
+    // Implement the "erroring, propagating" tree: a miss calls the caller-provided function with
+    // the unmatched value and `return`s the result from the enclosing function, converted via
+    // `From`, the way `?` would
+    {
+        @error
+        const $const_var:ident: $const_ty:ty = $match_val:expr => $($const_expr:expr),+ , else $err:expr;
+        $rest:tt
+        $block:block
+    } => {
+        match $match_val {
+            $(
+            $const_expr => {
+                const $const_var: $const_ty = $const_expr;
+                $crate::__impl_constify! { @error $rest $block }
+            }
+            )*
+            #[allow(unreachable_patterns)]
+            other => return ::core::result::Result::Err(::core::convert::From::from(($err)(other))),
+        }
+    };
+
     // Implement the "erroring" tree, which returns an error on missing variants
     {
         @error
-        const $const_var:ident: $const_ty:ty = $match_val:expr => $($const_expr:expr),+;
+        const $const_var:ident: $const_ty:ty = $match_val:expr => $($const_expr:expr),+ $(, : $msg:literal)?;
         $rest:tt
         $block:block
     } => {
@@ -63,7 +157,7 @@ macro_rules! __impl_constify {
             }
             )*
             #[allow(unreachable_patterns)]
-            _ => ::core::result::Result::Err(concat!("unexpected value for `", stringify!($const_var), "`"))
+            other => $crate::__constify_err!($const_var, other $(, $msg)?),
         }
     };
 
@@ -79,7 +173,10 @@ macro_rules! __impl_constify {
 ///
 /// This macro compares runtime expressions to the provided constants and evaluates the given
 /// expression with the matching constants.
-/// All cases must be covered; to match only a subset of values use [`try_constify`].
+/// All cases must be covered, unless the returned expression is followed by `; default $expr`
+/// (mirroring the no-argument form of [`panic!`]), in which case `$expr` is evaluated for any
+/// runtime value that doesn't match one of the provided constants. To instead return an error on
+/// a miss, use [`try_constify`].
 ///
 /// The expressions are evaluated in the order they are provided.
 ///
@@ -111,32 +208,51 @@ macro_rules! __impl_constify {
 /// assert_eq!(sum(3, 4, false, true), 4);
 /// assert_eq!(sum(3, 4, true, true), 7);
 /// ```
+///
+/// An example with a `default` fallback, for when only the common cases are worth
+/// const-specializing:
+/// ```
+/// fn lanes_impl<const N: usize>(x: usize) -> usize {
+///     x * N
+/// }
+///
+/// fn lanes_runtime(x: usize, n: usize) -> usize {
+///     x * n
+/// }
+///
+/// fn lanes(x: usize, n: usize) -> usize {
+///     constify::constify! (
+///         const N: usize = n => 1, 2, 4;
+///
+///         lanes_impl::<N>(x);
+///         default lanes_runtime(x, n)
+///     )
+/// }
+///
+/// assert_eq!(lanes(2, 4), 8); // const-specialized
+/// assert_eq!(lanes(2, 3), 6); // falls back to the runtime implementation
+/// ```
 #[macro_export]
 macro_rules! constify {
-    {
-        $(
-        const $const_var:ident: $const_ty:ty = $match_val:expr => $($const_expr:expr),+;
-        )*
-
-        $return:expr
-    } => {
-        $crate::__impl_constify! {
-            @normal
-            [$({const $const_var: $const_ty = $match_val => $($const_expr),*;})*]
-
-            { $return }
-        }
+    { $($input:tt)* } => {
+        $crate::__constify_munch! { @normal [] $($input)* }
     }
 }
 
 /// Fallibly convert runtime values to `const`s.
 ///
-/// This macro is identical to [`constify`], except it returns a `Result<_, &'static str>`.
+/// This macro is identical to [`constify`], except it returns a `Result<_, &'static str>` (or
+/// `Result<_, alloc::string::String>` if the `alloc` feature is enabled).
 /// If all of the runtime expressions evaluate to one of the provided constants, `Ok(_)` is
 /// returned.
 /// If any of the runtime values don't match any of the provided constants, `Err(msg)` is returned,
 /// and `msg` contains a description of which constant failed the match.
 ///
+/// A custom message can be given for a variable by following its constant list with
+/// `, : "message"`. With the `alloc` feature enabled, the message is a format string into which
+/// the offending runtime value is formatted, so it must contain a `{}` placeholder; without the
+/// feature, the message is returned as-is, placeholder and all.
+///
 /// An example:
 /// ```should_panic
 /// fn add_impl<const A: u32, const B: u32>() -> u32 {
@@ -155,20 +271,104 @@ macro_rules! constify {
 /// assert_eq!(add(1, 3), 4); // This is OK
 /// assert_eq!(add(3, 3), 6); // This panics, since `a` is out of range!
 /// ```
+///
+/// An example with a custom message:
+/// ```should_panic
+/// fn lanes_impl<const N: usize>() -> usize {
+///     N
+/// }
+///
+/// fn lanes(n: usize) -> usize {
+///     constify::try_constify! (
+///         const N: usize = n => 1, 2, 4, : "unsupported lane count: {}";
+///
+///         lanes_impl::<N>()
+///     ).unwrap()
+/// }
+///
+/// assert_eq!(lanes(4), 4); // This is OK
+/// assert_eq!(lanes(3), 3); // This panics; by default (without `alloc`) the message is returned
+///                          // exactly as given, placeholder and all: "unsupported lane count: {}"
+/// ```
+///
+/// With the `alloc` feature enabled, the message is instead formatted with the offending value:
+/// ```
+/// #[cfg(feature = "alloc")]
+/// fn lanes_impl<const N: usize>() -> usize {
+///     N
+/// }
+///
+/// #[cfg(feature = "alloc")]
+/// fn lanes(n: usize) -> Result<usize, String> {
+///     constify::try_constify! (
+///         const N: usize = n => 1, 2, 4, : "unsupported lane count: {}";
+///
+///         lanes_impl::<N>()
+///     )
+/// }
+///
+/// #[cfg(feature = "alloc")]
+/// {
+///     assert_eq!(lanes(4), Ok(4));
+///     assert_eq!(lanes(3), Err("unsupported lane count: 3".to_string()));
+/// }
+/// ```
+///
+/// A variable's constant list can instead be followed by `, else $err`, naming a function or
+/// closure that's called with the unmatched runtime value to build the error returned on a miss.
+/// On a miss, the result is converted with [`From`] and `return`ed from the enclosing function, as
+/// `?` would, so that function may return any error type the caller's error can convert into. The
+/// invocation itself evaluates to `Ok(_)`, whose error type is inferred from its context (such as
+/// the function's tail expression); applying `?` to the invocation leaves that type ambiguous:
+/// ```
+/// #[derive(Debug)]
+/// enum Error {
+///     BadLaneCount(u32),
+/// }
+///
+/// fn lanes_impl<const N: u32>() -> u32 {
+///     N
+/// }
+///
+/// fn lanes(n: u32) -> Result<u32, Error> {
+///     constify::try_constify! (
+///         const N: u32 = n => 1, 2, 4, else Error::BadLaneCount;
+///
+///         lanes_impl::<N>()
+///     )
+/// }
+///
+/// assert_eq!(lanes(4).unwrap(), 4);
+/// assert!(lanes(3).is_err());
+/// ```
+///
+/// A constant list may also be given as a range, `$start..=$end` or `$start..=$end step $step`,
+/// which expands to the same match arms as listing every value explicitly, including an optional
+/// trailing `: $msg` or `else $err` clause (but not both at once). Enumerating a range recurses a
+/// number of times that grows linearly with both `$start` and `$end - $start`, and only modestly
+/// less with a larger `$step`, so a range with a wide span (not necessarily a long one) or a high
+/// start may need the invoking crate to raise `#![recursion_limit]`:
+/// ```
+/// #![recursion_limit = "1024"]
+/// fn lanes_impl<const N: usize>(x: usize) -> usize {
+///     x * N
+/// }
+///
+/// fn lanes(x: usize, n: usize) -> Option<usize> {
+///     constify::try_constify! (
+///         const N: usize = n => 0..=64 step 8;
+///
+///         lanes_impl::<N>(x)
+///     )
+///     .ok()
+/// }
+///
+/// assert_eq!(lanes(2, 16), Some(32));
+/// assert_eq!(lanes(2, 17), None);
+/// ```
 #[macro_export]
 macro_rules! try_constify {
-    {
-        $(
-        const $const_var:ident: $const_ty:ty = $match_val:expr => $($const_expr:expr),+;
-        )*
-
-        $return:expr
-    } => {
-        $crate::__impl_constify! {
-            @error
-            [$({const $const_var: $const_ty = $match_val => $($const_expr),*;})*]
-
-            { $return }
-        }
+    { $($input:tt)* } => {
+        $crate::__constify_munch! { @error [] $($input)* }
     }
 }
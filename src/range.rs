@@ -0,0 +1,391 @@
+//! Bounded compile-time integer arithmetic used to expand `a..=b` / `a..=b step s`
+//! range syntax in [`constify`](crate::constify)/[`try_constify`](crate::try_constify) into one
+//! match arm per enumerated value, at macro-expansion time.
+//!
+//! `macro_rules!` has no arithmetic or comparison operators, so every operation below is a
+//! lookup table over literal integers, bounded to `0..=64`. Successor and predecessor are
+//! single lookups; addition and comparison are built by chaining them one step at a time via
+//! continuation-passing style (each macro takes a callback macro and invokes it with the
+//! result, since a macro invocation can't be used as a nested argument of another).
+
+// $a + 1
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_succ_cps {
+    ([$($cb:tt)+] ($($extra:tt)*) 0) => { $($cb)+!($($extra)* 1) };
+    ([$($cb:tt)+] ($($extra:tt)*) 1) => { $($cb)+!($($extra)* 2) };
+    ([$($cb:tt)+] ($($extra:tt)*) 2) => { $($cb)+!($($extra)* 3) };
+    ([$($cb:tt)+] ($($extra:tt)*) 3) => { $($cb)+!($($extra)* 4) };
+    ([$($cb:tt)+] ($($extra:tt)*) 4) => { $($cb)+!($($extra)* 5) };
+    ([$($cb:tt)+] ($($extra:tt)*) 5) => { $($cb)+!($($extra)* 6) };
+    ([$($cb:tt)+] ($($extra:tt)*) 6) => { $($cb)+!($($extra)* 7) };
+    ([$($cb:tt)+] ($($extra:tt)*) 7) => { $($cb)+!($($extra)* 8) };
+    ([$($cb:tt)+] ($($extra:tt)*) 8) => { $($cb)+!($($extra)* 9) };
+    ([$($cb:tt)+] ($($extra:tt)*) 9) => { $($cb)+!($($extra)* 10) };
+    ([$($cb:tt)+] ($($extra:tt)*) 10) => { $($cb)+!($($extra)* 11) };
+    ([$($cb:tt)+] ($($extra:tt)*) 11) => { $($cb)+!($($extra)* 12) };
+    ([$($cb:tt)+] ($($extra:tt)*) 12) => { $($cb)+!($($extra)* 13) };
+    ([$($cb:tt)+] ($($extra:tt)*) 13) => { $($cb)+!($($extra)* 14) };
+    ([$($cb:tt)+] ($($extra:tt)*) 14) => { $($cb)+!($($extra)* 15) };
+    ([$($cb:tt)+] ($($extra:tt)*) 15) => { $($cb)+!($($extra)* 16) };
+    ([$($cb:tt)+] ($($extra:tt)*) 16) => { $($cb)+!($($extra)* 17) };
+    ([$($cb:tt)+] ($($extra:tt)*) 17) => { $($cb)+!($($extra)* 18) };
+    ([$($cb:tt)+] ($($extra:tt)*) 18) => { $($cb)+!($($extra)* 19) };
+    ([$($cb:tt)+] ($($extra:tt)*) 19) => { $($cb)+!($($extra)* 20) };
+    ([$($cb:tt)+] ($($extra:tt)*) 20) => { $($cb)+!($($extra)* 21) };
+    ([$($cb:tt)+] ($($extra:tt)*) 21) => { $($cb)+!($($extra)* 22) };
+    ([$($cb:tt)+] ($($extra:tt)*) 22) => { $($cb)+!($($extra)* 23) };
+    ([$($cb:tt)+] ($($extra:tt)*) 23) => { $($cb)+!($($extra)* 24) };
+    ([$($cb:tt)+] ($($extra:tt)*) 24) => { $($cb)+!($($extra)* 25) };
+    ([$($cb:tt)+] ($($extra:tt)*) 25) => { $($cb)+!($($extra)* 26) };
+    ([$($cb:tt)+] ($($extra:tt)*) 26) => { $($cb)+!($($extra)* 27) };
+    ([$($cb:tt)+] ($($extra:tt)*) 27) => { $($cb)+!($($extra)* 28) };
+    ([$($cb:tt)+] ($($extra:tt)*) 28) => { $($cb)+!($($extra)* 29) };
+    ([$($cb:tt)+] ($($extra:tt)*) 29) => { $($cb)+!($($extra)* 30) };
+    ([$($cb:tt)+] ($($extra:tt)*) 30) => { $($cb)+!($($extra)* 31) };
+    ([$($cb:tt)+] ($($extra:tt)*) 31) => { $($cb)+!($($extra)* 32) };
+    ([$($cb:tt)+] ($($extra:tt)*) 32) => { $($cb)+!($($extra)* 33) };
+    ([$($cb:tt)+] ($($extra:tt)*) 33) => { $($cb)+!($($extra)* 34) };
+    ([$($cb:tt)+] ($($extra:tt)*) 34) => { $($cb)+!($($extra)* 35) };
+    ([$($cb:tt)+] ($($extra:tt)*) 35) => { $($cb)+!($($extra)* 36) };
+    ([$($cb:tt)+] ($($extra:tt)*) 36) => { $($cb)+!($($extra)* 37) };
+    ([$($cb:tt)+] ($($extra:tt)*) 37) => { $($cb)+!($($extra)* 38) };
+    ([$($cb:tt)+] ($($extra:tt)*) 38) => { $($cb)+!($($extra)* 39) };
+    ([$($cb:tt)+] ($($extra:tt)*) 39) => { $($cb)+!($($extra)* 40) };
+    ([$($cb:tt)+] ($($extra:tt)*) 40) => { $($cb)+!($($extra)* 41) };
+    ([$($cb:tt)+] ($($extra:tt)*) 41) => { $($cb)+!($($extra)* 42) };
+    ([$($cb:tt)+] ($($extra:tt)*) 42) => { $($cb)+!($($extra)* 43) };
+    ([$($cb:tt)+] ($($extra:tt)*) 43) => { $($cb)+!($($extra)* 44) };
+    ([$($cb:tt)+] ($($extra:tt)*) 44) => { $($cb)+!($($extra)* 45) };
+    ([$($cb:tt)+] ($($extra:tt)*) 45) => { $($cb)+!($($extra)* 46) };
+    ([$($cb:tt)+] ($($extra:tt)*) 46) => { $($cb)+!($($extra)* 47) };
+    ([$($cb:tt)+] ($($extra:tt)*) 47) => { $($cb)+!($($extra)* 48) };
+    ([$($cb:tt)+] ($($extra:tt)*) 48) => { $($cb)+!($($extra)* 49) };
+    ([$($cb:tt)+] ($($extra:tt)*) 49) => { $($cb)+!($($extra)* 50) };
+    ([$($cb:tt)+] ($($extra:tt)*) 50) => { $($cb)+!($($extra)* 51) };
+    ([$($cb:tt)+] ($($extra:tt)*) 51) => { $($cb)+!($($extra)* 52) };
+    ([$($cb:tt)+] ($($extra:tt)*) 52) => { $($cb)+!($($extra)* 53) };
+    ([$($cb:tt)+] ($($extra:tt)*) 53) => { $($cb)+!($($extra)* 54) };
+    ([$($cb:tt)+] ($($extra:tt)*) 54) => { $($cb)+!($($extra)* 55) };
+    ([$($cb:tt)+] ($($extra:tt)*) 55) => { $($cb)+!($($extra)* 56) };
+    ([$($cb:tt)+] ($($extra:tt)*) 56) => { $($cb)+!($($extra)* 57) };
+    ([$($cb:tt)+] ($($extra:tt)*) 57) => { $($cb)+!($($extra)* 58) };
+    ([$($cb:tt)+] ($($extra:tt)*) 58) => { $($cb)+!($($extra)* 59) };
+    ([$($cb:tt)+] ($($extra:tt)*) 59) => { $($cb)+!($($extra)* 60) };
+    ([$($cb:tt)+] ($($extra:tt)*) 60) => { $($cb)+!($($extra)* 61) };
+    ([$($cb:tt)+] ($($extra:tt)*) 61) => { $($cb)+!($($extra)* 62) };
+    ([$($cb:tt)+] ($($extra:tt)*) 62) => { $($cb)+!($($extra)* 63) };
+    ([$($cb:tt)+] ($($extra:tt)*) 63) => { $($cb)+!($($extra)* 64) };
+}
+
+// $a - 1
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_pred_cps {
+    ([$($cb:tt)+] ($($extra:tt)*) 1) => { $($cb)+!($($extra)* 0) };
+    ([$($cb:tt)+] ($($extra:tt)*) 2) => { $($cb)+!($($extra)* 1) };
+    ([$($cb:tt)+] ($($extra:tt)*) 3) => { $($cb)+!($($extra)* 2) };
+    ([$($cb:tt)+] ($($extra:tt)*) 4) => { $($cb)+!($($extra)* 3) };
+    ([$($cb:tt)+] ($($extra:tt)*) 5) => { $($cb)+!($($extra)* 4) };
+    ([$($cb:tt)+] ($($extra:tt)*) 6) => { $($cb)+!($($extra)* 5) };
+    ([$($cb:tt)+] ($($extra:tt)*) 7) => { $($cb)+!($($extra)* 6) };
+    ([$($cb:tt)+] ($($extra:tt)*) 8) => { $($cb)+!($($extra)* 7) };
+    ([$($cb:tt)+] ($($extra:tt)*) 9) => { $($cb)+!($($extra)* 8) };
+    ([$($cb:tt)+] ($($extra:tt)*) 10) => { $($cb)+!($($extra)* 9) };
+    ([$($cb:tt)+] ($($extra:tt)*) 11) => { $($cb)+!($($extra)* 10) };
+    ([$($cb:tt)+] ($($extra:tt)*) 12) => { $($cb)+!($($extra)* 11) };
+    ([$($cb:tt)+] ($($extra:tt)*) 13) => { $($cb)+!($($extra)* 12) };
+    ([$($cb:tt)+] ($($extra:tt)*) 14) => { $($cb)+!($($extra)* 13) };
+    ([$($cb:tt)+] ($($extra:tt)*) 15) => { $($cb)+!($($extra)* 14) };
+    ([$($cb:tt)+] ($($extra:tt)*) 16) => { $($cb)+!($($extra)* 15) };
+    ([$($cb:tt)+] ($($extra:tt)*) 17) => { $($cb)+!($($extra)* 16) };
+    ([$($cb:tt)+] ($($extra:tt)*) 18) => { $($cb)+!($($extra)* 17) };
+    ([$($cb:tt)+] ($($extra:tt)*) 19) => { $($cb)+!($($extra)* 18) };
+    ([$($cb:tt)+] ($($extra:tt)*) 20) => { $($cb)+!($($extra)* 19) };
+    ([$($cb:tt)+] ($($extra:tt)*) 21) => { $($cb)+!($($extra)* 20) };
+    ([$($cb:tt)+] ($($extra:tt)*) 22) => { $($cb)+!($($extra)* 21) };
+    ([$($cb:tt)+] ($($extra:tt)*) 23) => { $($cb)+!($($extra)* 22) };
+    ([$($cb:tt)+] ($($extra:tt)*) 24) => { $($cb)+!($($extra)* 23) };
+    ([$($cb:tt)+] ($($extra:tt)*) 25) => { $($cb)+!($($extra)* 24) };
+    ([$($cb:tt)+] ($($extra:tt)*) 26) => { $($cb)+!($($extra)* 25) };
+    ([$($cb:tt)+] ($($extra:tt)*) 27) => { $($cb)+!($($extra)* 26) };
+    ([$($cb:tt)+] ($($extra:tt)*) 28) => { $($cb)+!($($extra)* 27) };
+    ([$($cb:tt)+] ($($extra:tt)*) 29) => { $($cb)+!($($extra)* 28) };
+    ([$($cb:tt)+] ($($extra:tt)*) 30) => { $($cb)+!($($extra)* 29) };
+    ([$($cb:tt)+] ($($extra:tt)*) 31) => { $($cb)+!($($extra)* 30) };
+    ([$($cb:tt)+] ($($extra:tt)*) 32) => { $($cb)+!($($extra)* 31) };
+    ([$($cb:tt)+] ($($extra:tt)*) 33) => { $($cb)+!($($extra)* 32) };
+    ([$($cb:tt)+] ($($extra:tt)*) 34) => { $($cb)+!($($extra)* 33) };
+    ([$($cb:tt)+] ($($extra:tt)*) 35) => { $($cb)+!($($extra)* 34) };
+    ([$($cb:tt)+] ($($extra:tt)*) 36) => { $($cb)+!($($extra)* 35) };
+    ([$($cb:tt)+] ($($extra:tt)*) 37) => { $($cb)+!($($extra)* 36) };
+    ([$($cb:tt)+] ($($extra:tt)*) 38) => { $($cb)+!($($extra)* 37) };
+    ([$($cb:tt)+] ($($extra:tt)*) 39) => { $($cb)+!($($extra)* 38) };
+    ([$($cb:tt)+] ($($extra:tt)*) 40) => { $($cb)+!($($extra)* 39) };
+    ([$($cb:tt)+] ($($extra:tt)*) 41) => { $($cb)+!($($extra)* 40) };
+    ([$($cb:tt)+] ($($extra:tt)*) 42) => { $($cb)+!($($extra)* 41) };
+    ([$($cb:tt)+] ($($extra:tt)*) 43) => { $($cb)+!($($extra)* 42) };
+    ([$($cb:tt)+] ($($extra:tt)*) 44) => { $($cb)+!($($extra)* 43) };
+    ([$($cb:tt)+] ($($extra:tt)*) 45) => { $($cb)+!($($extra)* 44) };
+    ([$($cb:tt)+] ($($extra:tt)*) 46) => { $($cb)+!($($extra)* 45) };
+    ([$($cb:tt)+] ($($extra:tt)*) 47) => { $($cb)+!($($extra)* 46) };
+    ([$($cb:tt)+] ($($extra:tt)*) 48) => { $($cb)+!($($extra)* 47) };
+    ([$($cb:tt)+] ($($extra:tt)*) 49) => { $($cb)+!($($extra)* 48) };
+    ([$($cb:tt)+] ($($extra:tt)*) 50) => { $($cb)+!($($extra)* 49) };
+    ([$($cb:tt)+] ($($extra:tt)*) 51) => { $($cb)+!($($extra)* 50) };
+    ([$($cb:tt)+] ($($extra:tt)*) 52) => { $($cb)+!($($extra)* 51) };
+    ([$($cb:tt)+] ($($extra:tt)*) 53) => { $($cb)+!($($extra)* 52) };
+    ([$($cb:tt)+] ($($extra:tt)*) 54) => { $($cb)+!($($extra)* 53) };
+    ([$($cb:tt)+] ($($extra:tt)*) 55) => { $($cb)+!($($extra)* 54) };
+    ([$($cb:tt)+] ($($extra:tt)*) 56) => { $($cb)+!($($extra)* 55) };
+    ([$($cb:tt)+] ($($extra:tt)*) 57) => { $($cb)+!($($extra)* 56) };
+    ([$($cb:tt)+] ($($extra:tt)*) 58) => { $($cb)+!($($extra)* 57) };
+    ([$($cb:tt)+] ($($extra:tt)*) 59) => { $($cb)+!($($extra)* 58) };
+    ([$($cb:tt)+] ($($extra:tt)*) 60) => { $($cb)+!($($extra)* 59) };
+    ([$($cb:tt)+] ($($extra:tt)*) 61) => { $($cb)+!($($extra)* 60) };
+    ([$($cb:tt)+] ($($extra:tt)*) 62) => { $($cb)+!($($extra)* 61) };
+    ([$($cb:tt)+] ($($extra:tt)*) 63) => { $($cb)+!($($extra)* 62) };
+    ([$($cb:tt)+] ($($extra:tt)*) 64) => { $($cb)+!($($extra)* 63) };
+}
+
+// $a + $n, by repeated successor. Threads the result to a callback instead of returning it
+// directly, since `$crate::__constify_succ_cps!(...)` can't be nested as another macro's argument.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_add_cps {
+    ([$($cb:tt)+] ($($extra:tt)*) $a:tt 0) => { $($cb)+!($($extra)* $a) };
+    ([$($cb:tt)+] ($($extra:tt)*) $a:tt $n:tt) => {
+        $crate::__constify_succ_cps!([$crate::__constify_add_cps_step] ([$($cb)+] ($($extra)*) $n) $a)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_add_cps_step {
+    ([$($cb:tt)+] ($($extra:tt)*) $n:tt $a2:tt) => {
+        $crate::__constify_pred_cps!([$crate::__constify_add_cps] ([$($cb)+] ($($extra)*) $a2) $n)
+    };
+}
+
+// Compares $a to $b by simultaneous predecessor, threading `lt`, `eq`, or `gt` to the callback.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_cmp_cps {
+    ([$($cb:tt)+] ($($extra:tt)*) 0 0) => { $($cb)+!($($extra)* eq) };
+    ([$($cb:tt)+] ($($extra:tt)*) 0 $b:tt) => { $($cb)+!($($extra)* lt) };
+    ([$($cb:tt)+] ($($extra:tt)*) $a:tt 0) => { $($cb)+!($($extra)* gt) };
+    ([$($cb:tt)+] ($($extra:tt)*) $a:tt $b:tt) => {
+        $crate::__constify_pred_cps!([$crate::__constify_cmp_cps_step] ([$($cb)+] ($($extra)*) $b) $a)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_cmp_cps_step {
+    ([$($cb:tt)+] ($($extra:tt)*) $b:tt $a2:tt) => {
+        $crate::__constify_pred_cps!([$crate::__constify_cmp_cps] ([$($cb)+] ($($extra)*) $a2) $b)
+    };
+}
+
+// $a - $b, assuming $a >= $b, by simultaneous predecessor. Used once per range to turn
+// `$start..=$end` into a countdown, so the loop below can track "steps remaining" instead of
+// re-deriving it by comparing the growing `$current` against `$end` on every iteration.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_diff_cps {
+    ([$($cb:tt)+] ($($extra:tt)*) $a:tt 0) => { $($cb)+!($($extra)* $a) };
+    ([$($cb:tt)+] ($($extra:tt)*) $a:tt $b:tt) => {
+        $crate::__constify_pred_cps!([$crate::__constify_diff_cps_step] ([$($cb)+] ($($extra)*) $b) $a)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_diff_cps_step {
+    ([$($cb:tt)+] ($($extra:tt)*) $b:tt $a2:tt) => {
+        $crate::__constify_pred_cps!([$crate::__constify_diff_cps] ([$($cb)+] ($($extra)*) $a2) $b)
+    };
+}
+
+// `$remaining - $step`, threading `some $new_remaining` to the callback if `$step <= $remaining`,
+// or plain `none` if `$step` would underflow it. Unlike [`__constify_cmp_cps`], the cost of this
+// check is bounded by `$step`, not by how far the countdown has already progressed, which is what
+// keeps a whole range's enumeration cost proportional to the range's span (`$end - $start`) rather
+// than to the square of it.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_try_sub_cps {
+    ([$($cb:tt)+] ($($extra:tt)*) $remaining:tt 0) => { $($cb)+!($($extra)* some $remaining) };
+    ([$($cb:tt)+] ($($extra:tt)*) 0 $step:tt) => { $($cb)+!($($extra)* none) };
+    ([$($cb:tt)+] ($($extra:tt)*) $remaining:tt $step:tt) => {
+        $crate::__constify_pred_cps!([$crate::__constify_try_sub_cps_step] ([$($cb)+] ($($extra)*) $step) $remaining)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_try_sub_cps_step {
+    ([$($cb:tt)+] ($($extra:tt)*) $step:tt $remaining2:tt) => {
+        $crate::__constify_pred_cps!([$crate::__constify_try_sub_cps] ([$($cb)+] ($($extra)*) $remaining2) $step)
+    };
+}
+
+// Enumerates `$start, $start + $step, ..` up to and including `$end`, accumulating the visited
+// literals and handing the finished list to the `__constify_range_done!` callback carried in
+// `$ctx`. Rejects `$start > $end` up front via a `compile_error!`.
+//
+// The countdown ("steps remaining to `$end`") is computed once up front, and each iteration only
+// checks it against `$step`, rather than comparing the growing `$current` against `$end` from
+// scratch every time; this keeps the loop's expansion cost linear in the range's span
+// (`$end - $start`) instead of quadratic. Validating the range and computing the countdown each
+// cost expansion steps linear in `$start`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_enumerate {
+    ($ctx:tt $start:tt $end:tt 0) => {
+        compile_error!("constify!: range step must be nonzero")
+    };
+    ($ctx:tt $start:tt $end:tt $step:tt) => {
+        $crate::__constify_cmp_cps!([$crate::__constify_enumerate_validated] ($ctx $start $end $step) $start $end)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_enumerate_validated {
+    ($ctx:tt $start:tt $end:tt $step:tt gt) => {
+        compile_error!("constify!: range start is greater than its end")
+    };
+    ($ctx:tt $start:tt $end:tt $step:tt eq) => {
+        $crate::__constify_range_done! { $ctx [$start] }
+    };
+    ($ctx:tt $start:tt $end:tt $step:tt lt) => {
+        $crate::__constify_diff_cps!([$crate::__constify_enumerate_countdown] ($ctx $start $step) $end $start)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_enumerate_countdown {
+    ($ctx:tt $current:tt $step:tt $remaining:tt) => {
+        $crate::__constify_enumerate_step! { $ctx [$current] $current $step $remaining }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_enumerate_step {
+    ($ctx:tt [$($acc:tt)*] $current:tt $step:tt $remaining:tt) => {
+        $crate::__constify_try_sub_cps!([$crate::__constify_enumerate_decide] ($ctx [$($acc)*] $current $step) $remaining $step)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_enumerate_decide {
+    ($ctx:tt [$($acc:tt)*] $current:tt $step:tt none) => {
+        $crate::__constify_range_done! { $ctx [$($acc)*] }
+    };
+    ($ctx:tt [$($acc:tt)*] $current:tt $step:tt some $remaining:tt) => {
+        $crate::__constify_add_cps!([$crate::__constify_enumerate_next] ($ctx [$($acc)*] $step $remaining) $current $step)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_enumerate_next {
+    ($ctx:tt [$($acc:tt)*] $step:tt $remaining:tt $next:tt) => {
+        $crate::__constify_enumerate_step! { $ctx [$($acc)* $next] $next $step $remaining }
+    };
+}
+
+// Resumes `__constify_munch!` with the enumerated literals spliced in as an explicit constant
+// list, indistinguishable from one the caller wrote out by hand. `$suffix` carries the range's
+// own (optional) `: $msg` / `else $err` clause through untouched, for the same reason.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_range_done {
+    ([@$mode:ident [$($out:tt)*] $const_var:ident: $const_ty:ty = $match_val:expr; [$($rest:tt)*] [$($suffix:tt)*]] [$($acc:tt)*]) => {
+        $crate::__constify_munch! {
+            @$mode
+            [$($out)* { const $const_var: $const_ty = $match_val => $($acc),* $($suffix)*; }]
+            $($rest)*
+        }
+    };
+}
+
+// Dispatches each `const` line of `constify!`/`try_constify!` to the right expansion: a `step`-ed
+// range, a default-step range, or an explicit constant list. Ranges are enumerated into an
+// explicit list before reaching [`__impl_constify`]; the two forms are otherwise indistinguishable
+// downstream, including their trailing `: $msg` / `else $err` clause, which a range forwards
+// as-is onto the enumerated list.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __constify_munch {
+    // Neither a range nor an explicit list can have both a custom message and an `else` error
+    // expression: catch that here, for both forms, with a clear diagnostic, rather than let it
+    // fall through to a confusing "no rules expected" error once it reaches `__impl_constify!`.
+    {
+        @$mode:ident [$($out:tt)*]
+        const $const_var:ident: $const_ty:ty = $match_val:expr => $start:tt ..= $end:tt $(step $step:tt)? , : $msg:literal else $err:expr;
+        $($rest:tt)*
+    } => {
+        compile_error!("constify!: a variable's constant list can have a `: message` or an `else err`, not both")
+    };
+
+    {
+        @$mode:ident [$($out:tt)*]
+        const $const_var:ident: $const_ty:ty = $match_val:expr => $($const_expr:expr),+ , : $msg:literal else $err:expr;
+        $($rest:tt)*
+    } => {
+        compile_error!("constify!: a variable's constant list can have a `: message` or an `else err`, not both")
+    };
+
+    {
+        @$mode:ident [$($out:tt)*]
+        const $const_var:ident: $const_ty:ty = $match_val:expr => $start:tt ..= $end:tt step $step:tt $(, $(: $msg:literal)? $(else $err:expr)?)?;
+        $($rest:tt)*
+    } => {
+        $crate::__constify_enumerate! {
+            [@$mode [$($out)*] $const_var: $const_ty = $match_val; [$($rest)*] [$(, $(: $msg)? $(else $err)?)?]]
+            $start $end $step
+        }
+    };
+
+    {
+        @$mode:ident [$($out:tt)*]
+        const $const_var:ident: $const_ty:ty = $match_val:expr => $start:tt ..= $end:tt $(, $(: $msg:literal)? $(else $err:expr)?)?;
+        $($rest:tt)*
+    } => {
+        $crate::__constify_enumerate! {
+            [@$mode [$($out)*] $const_var: $const_ty = $match_val; [$($rest)*] [$(, $(: $msg)? $(else $err)?)?]]
+            $start $end 1
+        }
+    };
+
+    {
+        @$mode:ident [$($out:tt)*]
+        const $const_var:ident: $const_ty:ty = $match_val:expr => $($const_expr:expr),+ $(, $(: $msg:literal)? $(else $err:expr)?)?;
+        $($rest:tt)*
+    } => {
+        $crate::__constify_munch! {
+            @$mode
+            [$($out)* { const $const_var: $const_ty = $match_val => $($const_expr),* $(, $(: $msg)? $(else $err)?)?; }]
+            $($rest)*
+        }
+    };
+
+    // A trailing `default $fallback` clause, mirroring `panic!()`'s no-argument fallback form,
+    // opts out of the "normal" tree's mandatory exhaustiveness in favor of a dynamic fallback.
+    {
+        @normal [$($out:tt)*] $return:expr ; default $fallback:expr
+    } => {
+        $crate::__impl_constify! { @fallback [$($out)*] { $return } { $fallback } }
+    };
+
+    {
+        @$mode:ident [$($out:tt)*] $return:expr
+    } => {
+        $crate::__impl_constify! { @$mode [$($out)*] { $return } }
+    };
+}